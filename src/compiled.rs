@@ -0,0 +1,209 @@
+use crate::pattern::{negate, MatchKind, Pattern, PathKind};
+use regex::RegexSet;
+
+/// Translates a single gitignore glob (already stripped of its leading `!` and
+/// surrounding `/`) into an equivalent regex body. `anchored` controls whether
+/// the result is pinned to the start of the path or may match at any depth;
+/// `allow_descendants` controls whether anything underneath the match (the
+/// contents of a matched directory) is included too.
+fn glob_to_regex(glob: &str, anchored: bool, allow_descendants: bool) -> String {
+    let mut body = String::new();
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    body.push_str("(?:.*/)?");
+                } else {
+                    body.push_str(".*");
+                }
+            }
+            '*' => body.push_str("[^/]*"),
+            '?' => body.push_str("[^/]"),
+            '[' => {
+                body.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    body.push('^');
+                }
+                for next in chars.by_ref() {
+                    body.push(next);
+                    if next == ']' {
+                        break;
+                    }
+                }
+            }
+            '.' | '+' | '(' | ')' | '|' | '^' | '$' | '{' | '}' | '\\' => {
+                body.push('\\');
+                body.push(c);
+            }
+            _ => body.push(c),
+        }
+    }
+
+    let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+    let suffix = if allow_descendants { "(?:/.*)?$" } else { "$" };
+
+    format!("{}{}{}", prefix, body, suffix)
+}
+
+/// Metadata for one pattern inside a [`CompiledGitignore`], indexed identically
+/// to the underlying `RegexSet` so a match index can be resolved back to it.
+struct CompiledPattern {
+    string: String,
+    negated: bool,
+}
+
+/// A blocking directory pattern, indexed identically to `blocking_dirs` so a
+/// match index can be resolved back to the original line it came from.
+struct BlockingDir {
+    /// Index into the `lines` originally passed to `compile`.
+    index: usize,
+    string: String,
+}
+
+/// Picks out, from `lines`, the *unambiguous* directory patterns (explicit
+/// trailing `/`) that ignore everything underneath them and cannot be
+/// overridden by a later `!` re-inclusion (mirrors git's "a deeper re-include
+/// cannot resurrect a path whose parent directory is ignored" rule). A
+/// pattern without a trailing `/` could equally be a plain file, so it is
+/// deliberately excluded here even though it otherwise behaves like
+/// `PathKind::Both` elsewhere in this module.
+fn find_ignored_dirs(lines: &[&str]) -> Vec<BlockingDir> {
+    let mut ignored_dirs = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let glob = Pattern::new(line);
+        let parents: Vec<String> = glob.get_parents().into_iter().map(|p| negate(&p)).collect();
+        let has_negated_parents = parents.iter().any(|p| lines.contains(&&p[..]));
+
+        if matches!(glob.path_kind, PathKind::Dir) && !glob.negated && !has_negated_parents {
+            ignored_dirs.push(BlockingDir {
+                index,
+                string: glob.string,
+            });
+        }
+    }
+
+    ignored_dirs
+}
+
+/// A whole pattern list compiled once into a single `regex::RegexSet`, so that
+/// checking many paths against it costs one set-match per path instead of
+/// re-parsing and re-compiling every glob every time.
+pub struct CompiledGitignore {
+    set: RegexSet,
+    patterns: Vec<CompiledPattern>,
+    /// Regexes for directories that are ignored and not re-includable, so a
+    /// path under one of them is ignored regardless of what any other
+    /// (including a later, negated) pattern in `set` would otherwise decide.
+    blocking_dirs: RegexSet,
+    /// Metadata for `blocking_dirs`, indexed identically to it.
+    blocking: Vec<BlockingDir>,
+}
+
+impl CompiledGitignore {
+    /// Compiles `lines` into a single matcher. Comment and blank lines must
+    /// already be filtered out by the caller.
+    pub fn compile(lines: &[&str]) -> CompiledGitignore {
+        let mut patterns = Vec::with_capacity(lines.len());
+        let mut regexes = Vec::with_capacity(lines.len());
+
+        for line in lines {
+            let glob = Pattern::new(line);
+            let anchored = matches!(glob.match_type, MatchKind::Relative);
+            let allow_descendants = !matches!(glob.path_kind, PathKind::File);
+            let body = glob.string.trim_matches('/');
+
+            regexes.push(glob_to_regex(body, anchored, allow_descendants));
+            patterns.push(CompiledPattern {
+                string: glob.string.clone(),
+                negated: glob.negated,
+            });
+        }
+
+        let blocking = find_ignored_dirs(lines);
+        let blocking_regexes: Vec<String> = blocking
+            .iter()
+            .map(|dir| {
+                let glob = Pattern::new(&dir.string);
+                let anchored = matches!(glob.match_type, MatchKind::Relative);
+                let body = glob.string.trim_matches('/');
+                glob_to_regex(body, anchored, true)
+            })
+            .collect();
+
+        let set = RegexSet::new(&regexes).expect("gitignore patterns translate to valid regexes");
+        let blocking_dirs =
+            RegexSet::new(&blocking_regexes).expect("gitignore patterns translate to valid regexes");
+
+        CompiledGitignore {
+            set,
+            patterns,
+            blocking_dirs,
+            blocking,
+        }
+    }
+
+    /// Matches `target`, a `/`-separated path relative to the root the patterns
+    /// were loaded against, in a single `RegexSet` pass, reporting the index
+    /// (into the `lines` originally passed to [`compile`](Self::compile)) and
+    /// text of the deciding pattern alongside whether it ignores (`true`) or
+    /// re-includes (`false`) the path. A path under an ignored,
+    /// non-re-includable directory is always ignored, regardless of any other
+    /// pattern. Otherwise, among every pattern that matches, the
+    /// highest-ordered one decides. Returns `None` if nothing in this list
+    /// matches at all, so callers combining several pattern lists can tell
+    /// "not mentioned here" apart from "re-included here".
+    pub fn matched(&self, target: &str) -> Option<(usize, &str, bool)> {
+        if let Some(i) = self.blocking_dirs.matches(target).into_iter().max() {
+            let dir = &self.blocking[i];
+            return Some((dir.index, &dir.string, true));
+        }
+
+        self.set.matches(target).into_iter().max().map(|i| {
+            let pattern = &self.patterns[i];
+            (i, pattern.string.as_str(), !pattern.negated)
+        })
+    }
+
+    /// Same as [`matched`](Self::matched), but collapses the outcome to a
+    /// bool for callers that don't need to know which pattern decided.
+    pub fn is_match(&self, target: &str) -> Option<bool> {
+        self.matched(target).map(|(_, _, ignored)| ignored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignored_directory_blocks_negation_of_its_contents() {
+        let compiled = CompiledGitignore::compile(&["lib/", "!lib/*.js"]);
+        assert_eq!(compiled.is_match("lib/include.js"), Some(true));
+
+        let compiled = CompiledGitignore::compile(&["lib/", "!lib/deep/include.js"]);
+        assert_eq!(compiled.is_match("lib/deep/include.js"), Some(true));
+
+        let compiled = CompiledGitignore::compile(&["/lib/", "!/lib/deep/"]);
+        assert_eq!(compiled.is_match("lib/deep/include.js"), Some(true));
+    }
+
+    #[test]
+    fn a_bare_name_without_trailing_slash_does_not_block_negation() {
+        // "lib" (no trailing slash) is ambiguous file-or-dir, so it must not
+        // get the cross-directory blocking treatment "lib/" gets.
+        let compiled = CompiledGitignore::compile(&["lib", "!lib/*.js"]);
+        assert_eq!(compiled.is_match("lib/include.js"), Some(false));
+    }
+
+    #[test]
+    fn no_match_returns_none() {
+        let compiled = CompiledGitignore::compile(&["*.log"]);
+        assert_eq!(compiled.is_match("src/main.rs"), None);
+    }
+}
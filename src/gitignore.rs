@@ -0,0 +1,443 @@
+use crate::compiled::CompiledGitignore;
+use glob::MatchOptions;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A `.gitignore` or `.ignore` file discovered on disk together with the directory it governs.
+/// Patterns in `compiled` are relative to `root`, not necessarily to the overall
+/// [`Gitignore::root`].
+pub(crate) struct IgnoreFile {
+    pub(crate) root: PathBuf,
+    pub(crate) compiled: CompiledGitignore,
+}
+
+/// Reads and compiles the `.gitignore` in `dir`, if any non-empty one exists.
+pub(crate) fn load_ignore_file(dir: &Path) -> io::Result<Option<IgnoreFile>> {
+    load_pattern_file(dir, ".gitignore")
+}
+
+/// Reads and compiles the `.ignore` in `dir`, if any non-empty one exists.
+/// Same format as `.gitignore`, but not tied to `.git` discovery, so it can be
+/// used to carry ignore rules for non-VCS tooling.
+pub(crate) fn load_ignore_override_file(dir: &Path) -> io::Result<Option<IgnoreFile>> {
+    load_pattern_file(dir, ".ignore")
+}
+
+fn load_pattern_file(dir: &Path, name: &str) -> io::Result<Option<IgnoreFile>> {
+    let candidate = dir.join(name);
+
+    if !candidate.is_file() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&candidate)?;
+    let lines: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+
+    Ok(Some(IgnoreFile {
+        root: dir.to_path_buf(),
+        compiled: CompiledGitignore::compile(&refs),
+    }))
+}
+
+/// The outcome of matching a path against a list of gitignore patterns, naming
+/// the specific pattern and its line index so callers can explain *why* a path
+/// was (not) ignored, e.g. for a `git check-ignore -v`-style diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Match {
+    /// A pattern ignored the path. Carries the deciding pattern and its index
+    /// in the provided list.
+    Ignore(String, usize),
+    /// A negated pattern re-included the path. Carries the deciding pattern
+    /// and its index in the provided list.
+    Whitelist(String, usize),
+    /// No pattern in the list matched the path.
+    None,
+}
+
+pub(crate) fn relative_to(root: &Path, target: &Path) -> Option<String> {
+    let relative = target.strip_prefix(root).ok()?;
+    let joined = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    Some(joined)
+}
+
+/// Whether `target` is ignored by `files`, consulted from shallowest to
+/// deepest so a deeper file's verdict (including a re-inclusion via `!`)
+/// overrides a shallower one for the paths it covers. Shared by
+/// [`Gitignore::ignores_path`] and the downward scan in
+/// [`Gitignore::from_path_with_sources`], which needs it to avoid descending
+/// into directories already ignored by the files collected so far.
+fn files_ignore(files: &[IgnoreFile], target: &Path) -> bool {
+    let mut applicable: Vec<&IgnoreFile> = files
+        .iter()
+        .filter(|file| target.strip_prefix(&file.root).is_ok())
+        .collect();
+
+    applicable.sort_by_key(|file| file.root.as_os_str().len());
+
+    let mut is_ignored = false;
+
+    for file in applicable {
+        if let Some(relative) = relative_to(&file.root, target) {
+            if let Some(ignored) = file.compiled.is_match(&relative) {
+                is_ignored = ignored;
+            }
+        }
+    }
+
+    is_ignored
+}
+
+/// Recursively loads every `.gitignore`/`.ignore` found under (but not
+/// including) `dir`, skipping directories already ignored by `files` so
+/// rules nested inside an ignored directory are never read.
+fn collect_descendant_ignore_files(
+    dir: &Path,
+    vcs_ignore: bool,
+    ignore_file: bool,
+    files: &mut Vec<IgnoreFile>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if !entry.file_type()?.is_dir() || files_ignore(files, &path) {
+            continue;
+        }
+
+        if vcs_ignore {
+            if let Some(file) = load_ignore_file(&path)? {
+                files.push(file);
+            }
+        }
+
+        if ignore_file {
+            if let Some(file) = load_ignore_override_file(&path)? {
+                files.push(file);
+            }
+        }
+
+        collect_descendant_ignore_files(&path, vcs_ignore, ignore_file, files)?;
+    }
+
+    Ok(())
+}
+
+/// Used to match globs against user-provided paths.
+pub struct Gitignore<P: AsRef<Path>> {
+    /// Current working directory if created with `Gitignore::default()`.
+    pub root: P,
+    pub(crate) files: Vec<IgnoreFile>,
+    overrides: Option<CompiledGitignore>,
+}
+
+impl Default for Gitignore<PathBuf> {
+    /// Creates a new instance using current working directory.
+    fn default() -> Self {
+        Self {
+            root: env::current_dir().unwrap(),
+            files: Vec::new(),
+            overrides: None,
+        }
+    }
+}
+
+impl Gitignore<PathBuf> {
+    /// Loads every `.gitignore` found by walking up from `start` towards the
+    /// repository root (or the filesystem root, if no `.git` directory is
+    /// found) and every `.gitignore` found by walking *down* from there into
+    /// every subdirectory, so that [`ignores_path`](Gitignore::ignores_path)
+    /// can be called for any path under `start` without the caller reading or
+    /// splitting any files itself.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use gitignored::Gitignore;
+    ///
+    /// let ig = Gitignore::from_path(".").unwrap();
+    /// assert!(!ig.ignores_path("src/main.rs"));
+    /// ```
+    pub fn from_path(start: impl AsRef<Path>) -> io::Result<Gitignore<PathBuf>> {
+        Self::from_path_with_sources(start, true, true)
+    }
+
+    /// Same as [`from_path`](Gitignore::from_path), but lets the caller skip
+    /// `.gitignore` (`vcs_ignore`) and/or `.ignore` (`ignore_file`) files
+    /// independently. These are the same source toggles
+    /// [`WalkBuilder`](crate::WalkBuilder) offers as `no_vcs_ignore`/`no_ignore`,
+    /// so a bare `Gitignore` can be built with them without going through a
+    /// `Walk`.
+    pub fn from_path_with_sources(
+        start: impl AsRef<Path>,
+        vcs_ignore: bool,
+        ignore_file: bool,
+    ) -> io::Result<Gitignore<PathBuf>> {
+        let mut ig = Self::from_ancestors_only(start, vcs_ignore, ignore_file)?;
+        collect_descendant_ignore_files(&ig.root.clone(), vcs_ignore, ignore_file, &mut ig.files)?;
+        Ok(ig)
+    }
+
+    /// Loads only the `.gitignore`/`.ignore` files found walking up from
+    /// `start`, without also discovering the ones nested in its
+    /// subdirectories. [`WalkBuilder`](crate::WalkBuilder) uses this instead
+    /// of [`from_path_with_sources`](Self::from_path_with_sources), because
+    /// `Walk` already discovers each subdirectory's own ignore files lazily,
+    /// as it descends into it; eagerly collecting them here too would load
+    /// and compile every nested file twice.
+    pub(crate) fn from_ancestors_only(
+        start: impl AsRef<Path>,
+        vcs_ignore: bool,
+        ignore_file: bool,
+    ) -> io::Result<Gitignore<PathBuf>> {
+        let start = start.as_ref();
+
+        let mut dir = if start.is_dir() {
+            start.to_path_buf()
+        } else {
+            start
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("."))
+        };
+
+        let root = dir.clone();
+        let mut files = Vec::new();
+
+        loop {
+            if vcs_ignore {
+                if let Some(file) = load_ignore_file(&dir)? {
+                    files.push(file);
+                }
+            }
+
+            if ignore_file {
+                if let Some(file) = load_ignore_override_file(&dir)? {
+                    files.push(file);
+                }
+            }
+
+            if dir.join(".git").is_dir() {
+                break;
+            }
+
+            match dir.parent() {
+                Some(parent) => dir = parent.to_path_buf(),
+                None => break,
+            }
+        }
+
+        Ok(Gitignore {
+            root,
+            files,
+            overrides: None,
+        })
+    }
+}
+
+impl<P: AsRef<Path>> Gitignore<P> {
+    /// Creates a new instance. Requires a path that serves as a root for all path calculations.
+    ///
+    /// `options` is accepted for backwards compatibility but no longer affects matching: every
+    /// match now goes through the same `regex::RegexSet`-backed [`CompiledGitignore`] engine
+    /// [`ignores_path`](Gitignore::ignores_path) uses, which has no notion of `glob::MatchOptions`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let options = MatchOptions::new();
+    /// let cwd = env::current_dir().unwrap();
+    /// let ig = Gitignore::new(cwd, options);
+    /// ```
+    pub fn new(root: P, _options: MatchOptions) -> Gitignore<P> {
+        Gitignore {
+            root,
+            files: Vec::new(),
+            overrides: None,
+        }
+    }
+
+    /// Registers an explicit list of allow/deny globs that is consulted last,
+    /// after every `.gitignore`/`.ignore` loaded from disk, and wins over
+    /// whatever they decided. Lines use gitignore syntax: a plain glob denies
+    /// (ignores) the path it matches, a `!`-prefixed glob allows (re-includes)
+    /// it. Blank and `#`-comment lines are ignored, same as in a `.gitignore`
+    /// file. Pass an empty slice to clear any previously registered overrides.
+    pub fn set_overrides(&mut self, lines: &[&str]) {
+        let lines: Vec<&str> = lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        self.overrides = if lines.is_empty() {
+            None
+        } else {
+            Some(CompiledGitignore::compile(&lines))
+        };
+    }
+
+    /// Matches the target against the provided list of gitignore patterns, same
+    /// as [`ignores`](Gitignore::ignores), but reports which pattern decided the
+    /// outcome instead of collapsing it to a bool. `lines` is compiled into a
+    /// [`CompiledGitignore`] once per call, the same `RegexSet`-backed engine
+    /// [`ignores_path`](Gitignore::ignores_path) uses, instead of re-parsing
+    /// and re-matching every glob with the `glob` crate on every call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gitignored::{Gitignore, Match};
+    ///
+    /// let mut ig = Gitignore::default();
+    /// let globs = vec!["lib/*.js", "!lib/include.js"];
+    /// assert_eq!(
+    ///     ig.matched(&globs, ig.root.join("lib/include.js")),
+    ///     Match::Whitelist("lib/include.js".to_string(), 1),
+    /// );
+    /// ```
+    pub fn matched(&mut self, lines: &[&str], target: impl AsRef<Path>) -> Match {
+        let compiled = CompiledGitignore::compile(lines);
+        let target = target.as_ref();
+
+        let relative = match relative_to(self.root.as_ref(), target) {
+            Some(relative) => relative,
+            None => return Match::None,
+        };
+
+        match compiled.matched(&relative) {
+            Some((index, pattern, true)) => Match::Ignore(pattern.to_string(), index),
+            Some((index, pattern, false)) => Match::Whitelist(pattern.to_string(), index),
+            None => Match::None,
+        }
+    }
+
+    /// Checks if the target is ignored by provided list of gitignore patterns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let globs = vec!["lib/*.js", "!lib/include.js"];
+    /// assert!(!ig.ignores(&globs, ig.root.join("lib/include.js")));
+    /// ```
+    pub fn ignores(&mut self, lines: &[&str], target: impl AsRef<Path>) -> bool {
+        matches!(self.matched(lines, target), Match::Ignore(..))
+    }
+
+    /// Checks if the target is ignored by the `.gitignore`/`.ignore` files
+    /// loaded via [`from_path`](Gitignore::from_path) and by any overrides
+    /// registered with [`set_overrides`](Gitignore::set_overrides); an
+    /// override that matches the target decides the result over every loaded
+    /// file. Each file's patterns were compiled
+    /// once at load time, so this costs one `RegexSet` pass per applicable file
+    /// rather than re-parsing every glob on every call.
+    ///
+    /// Only files whose directory is an ancestor of `target` apply, and they
+    /// are consulted from shallowest to deepest, so a deeper `.gitignore`'s
+    /// verdict (including a re-inclusion via `!`) overrides a shallower one
+    /// for the paths it covers.
+    pub fn ignores_path(&self, target: impl AsRef<Path>) -> bool {
+        let target = target.as_ref();
+        let is_ignored = files_ignore(&self.files, target);
+
+        if let Some(overrides) = &self.overrides {
+            if let Some(relative) = relative_to(self.root.as_ref(), target) {
+                if let Some(decided) = overrides.is_match(&relative) {
+                    return decided;
+                }
+            }
+        }
+
+        is_ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiple_lines() {
+        let mut ig = Gitignore::default();
+
+        let a = vec!["lib/", "!lib/*.js"];
+        let b = vec!["lib", "!lib/*.js"];
+        let c = vec!["!lib/*.js", "lib"];
+        let d = vec!["lib/", "!lib/deep/include.js"];
+        let e = vec!["/lib/", "!/lib/deep/"];
+
+        let f = vec!["lib/", "!/lib/"];
+
+        let g = vec!["!/lib/", "lib/"];
+        let h = vec!["**/remove-items.js"];
+        let i = vec!["remove-items*"];
+        let j = vec!["remove*, !remove-items.js"];
+
+        let k = vec!["lib/*.js", "!lib/include.js"];
+        let l = vec!["lib/*.js", "!lib/"];
+        let m = vec!["lib/", "!lib/"];
+        let n = vec!["lib/", "!/lib/"];
+
+        let o = vec!["*.js", "!lib.js"];
+        let p = vec!["src/*.js", "target/"];
+
+        assert!(ig.ignores(&a, ig.root.join("lib/include.js")));
+
+        assert!(ig.ignores(&c, ig.root.join("lib/include.js")));
+        assert!(ig.ignores(&d, ig.root.join("lib/deep/include.js")));
+        assert!(ig.ignores(&e, ig.root.join("lib/deep/include.js")));
+
+        assert!(ig.ignores(&g, ig.root.join("deep/lib/include.js")));
+        assert!(ig.ignores(&h, ig.root.join("deep/lib/remove-items.js")));
+        assert!(ig.ignores(&i, ig.root.join("deep/lib/remove-items.js")));
+        assert!(ig.ignores(&p, ig.root.join("src/lib.js")));
+
+        assert!(!ig.ignores(&j, ig.root.join("deep/lib/remove-items.js")));
+        assert!(!ig.ignores(&k, ig.root.join("lib/include.js")));
+        assert!(!ig.ignores(&l, ig.root.join("lib/include.js")));
+        assert!(!ig.ignores(&m, ig.root.join("lib/include.js")));
+        assert!(!ig.ignores(&n, ig.root.join("lib/include.js")));
+        assert!(!ig.ignores(&o, ig.root.join("src/lib.js")));
+        assert!(!ig.ignores(&b, ig.root.join("lib/include.js")));
+
+        assert!(ig.ignores(&d, ig.root.join("lib/deep/ignored.js")));
+        assert!(ig.ignores(&f, ig.root.join("deep/lib/include.js")));
+    }
+
+    #[test]
+    fn matched_reports_the_deciding_pattern_and_index() {
+        let mut ig = Gitignore::default();
+
+        let globs = vec!["lib/*.js", "!lib/include.js"];
+
+        assert_eq!(
+            ig.matched(&globs, ig.root.join("lib/other.js")),
+            Match::Ignore("lib/*.js".to_string(), 0),
+        );
+        assert_eq!(
+            ig.matched(&globs, ig.root.join("lib/include.js")),
+            Match::Whitelist("lib/include.js".to_string(), 1),
+        );
+        assert_eq!(
+            ig.matched(&globs, ig.root.join("src/main.rs")),
+            Match::None,
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use regex::Regex;
+use std::path::Path;
+
+fn first_char(string: &str) -> char {
+    string.chars().next().unwrap()
+}
+
+pub(crate) fn negate(string: &str) -> String {
+    format!("!{}", string)
+}
+
+fn has_no_middle_separators(string: &str) -> bool {
+    let segments: Vec<&str> = string.split("/").filter(|s| !s.is_empty()).collect();
+    segments.len() <= 1
+}
+
+fn remove_whitespace(s: &str) -> String {
+    s.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[derive(Debug)]
+pub(crate) enum MatchKind {
+    Anywhere,
+    Relative,
+}
+
+#[derive(Debug)]
+pub(crate) enum PathKind {
+    Dir,
+    File,
+    Both,
+}
+
+/// Represents a glob pattern and meta information about it.
+pub struct Pattern {
+    pub string: String,
+    pub(crate) match_type: MatchKind,
+    pub(crate) path_kind: PathKind,
+    pub(crate) negated: bool,
+}
+
+impl Pattern {
+    /// Creates a new Pattern that can be passed to <a href="/struct.Gitignore.html#method.ignores_path">ignores_path</a>.
+    /// Example:
+    /// ```
+    /// let ptn = Pattern::new("**/dist/*.js");
+    /// ```
+    pub fn new<P: AsRef<Path>>(glob: P) -> Self {
+        let has_extension = Regex::new(r"\.[^\*/\\]+$").unwrap();
+        let glob = glob.as_ref().to_str().unwrap_or("");
+        let negated = glob.starts_with("!");
+        let without_neg = if negated { &glob[1..] } else { glob };
+        let normalized_glob = remove_whitespace(without_neg);
+
+        let match_type = if !normalized_glob.starts_with("**")
+            && first_char(&normalized_glob) != '/'
+            && has_no_middle_separators(&normalized_glob)
+        {
+            MatchKind::Anywhere
+        } else {
+            MatchKind::Relative
+        };
+
+        let path_kind = if has_extension.is_match(&normalized_glob) {
+            PathKind::File
+        } else {
+            if normalized_glob.ends_with("/") {
+                PathKind::Dir
+            } else {
+                PathKind::Both
+            }
+        };
+
+        Self {
+            string: String::from(normalized_glob),
+            negated,
+            match_type,
+            path_kind,
+        }
+    }
+
+    pub(crate) fn get_parents(&self) -> Vec<String> {
+        let mut segments: Vec<&str> = self.string.split("/").collect();
+        let mut parents: Vec<String> = Vec::new();
+        while segments.len() > 1 {
+            let mut joined = segments[..segments.len() - 1].join("/");
+            joined.push_str("/");
+            if joined.starts_with("/") {
+                parents.push(joined);
+            } else {
+                parents.push(format!("/{}", joined));
+                parents.push(joined);
+            }
+            segments.pop();
+        }
+
+        parents.into_iter().filter(|p| !p.is_empty()).collect()
+    }
+}
@@ -0,0 +1,219 @@
+use crate::gitignore::{load_ignore_file, load_ignore_override_file, Gitignore};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A path yielded by [`Walk`], together with the metadata already fetched while
+/// descending.
+pub struct DirEntry {
+    path: PathBuf,
+    depth: usize,
+    file_type: fs::FileType,
+}
+
+impl DirEntry {
+    /// The full path of this entry.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// How many directories below the walk's root this entry sits.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The type of this entry, as reported by the filesystem.
+    pub fn file_type(&self) -> fs::FileType {
+        self.file_type
+    }
+}
+
+struct Frame {
+    read_dir: fs::ReadDir,
+    /// How many `.gitignore`/`.ignore` files this directory pushed onto `Walk::ignore`,
+    /// to be popped again once the frame is exhausted.
+    pushed: usize,
+}
+
+/// Recursively iterates a directory tree, yielding every entry that is not
+/// ignored. Ignored directories are pruned rather than descended into, and
+/// `.gitignore` files are picked up as the walk passes through the
+/// directories that contain them, so a file's status always reflects the
+/// nearest-enclosing rules.
+pub struct Walk {
+    root: PathBuf,
+    frames: Vec<Frame>,
+    ignore: Gitignore<PathBuf>,
+    started: bool,
+    vcs_ignore: bool,
+    ignore_file: bool,
+}
+
+impl Walk {
+    /// Starts a walk rooted at `root`, loading `.gitignore` files the same way
+    /// [`Gitignore::from_path`] does.
+    pub fn new(root: impl AsRef<Path>) -> io::Result<Walk> {
+        WalkBuilder::new(root).build()
+    }
+
+    fn push_dir(&mut self, dir: &Path) -> io::Result<()> {
+        let mut pushed = 0;
+
+        if self.vcs_ignore {
+            if let Some(file) = load_ignore_file(dir)? {
+                self.ignore.files.push(file);
+                pushed += 1;
+            }
+        }
+
+        if self.ignore_file {
+            if let Some(file) = load_ignore_override_file(dir)? {
+                self.ignore.files.push(file);
+                pushed += 1;
+            }
+        }
+
+        let read_dir = fs::read_dir(dir)?;
+        self.frames.push(Frame { read_dir, pushed });
+
+        Ok(())
+    }
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+
+            let file_type = match fs::metadata(&self.root) {
+                Ok(metadata) => metadata.file_type(),
+                Err(e) => return Some(Err(e)),
+            };
+
+            match fs::read_dir(&self.root) {
+                Ok(read_dir) => self.frames.push(Frame {
+                    read_dir,
+                    pushed: 0,
+                }),
+                Err(e) => return Some(Err(e)),
+            }
+
+            return Some(Ok(DirEntry {
+                path: self.root.clone(),
+                depth: 0,
+                file_type,
+            }));
+        }
+
+        loop {
+            let frame = self.frames.last_mut()?;
+
+            let entry = match frame.read_dir.next() {
+                None => {
+                    let frame = self.frames.pop().unwrap();
+                    for _ in 0..frame.pushed {
+                        self.ignore.files.pop();
+                    }
+                    continue;
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(entry)) => entry,
+            };
+
+            let path = entry.path();
+
+            if self.ignore.ignores_path(&path) {
+                continue;
+            }
+
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let depth = self.frames.len();
+
+            if file_type.is_dir() {
+                if let Err(e) = self.push_dir(&path) {
+                    return Some(Err(e));
+                }
+            }
+
+            return Some(Ok(DirEntry {
+                path,
+                depth,
+                file_type,
+            }));
+        }
+    }
+}
+
+/// Builds a [`Walk`]. Kept separate from `Walk` itself so walk-wide options
+/// (which `.gitignore`-like sources to respect, explicit overrides, ...) have
+/// somewhere to live without cluttering the iterator.
+pub struct WalkBuilder {
+    root: PathBuf,
+    vcs_ignore: bool,
+    ignore_file: bool,
+    overrides: Vec<String>,
+}
+
+impl WalkBuilder {
+    /// Creates a builder for a walk rooted at `root`. By default both
+    /// `.gitignore` and `.ignore` files are respected and no overrides are set.
+    pub fn new(root: impl AsRef<Path>) -> WalkBuilder {
+        WalkBuilder {
+            root: root.as_ref().to_path_buf(),
+            vcs_ignore: true,
+            ignore_file: true,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// When `yes` is `true`, `.gitignore` files are not loaded.
+    pub fn no_vcs_ignore(mut self, yes: bool) -> WalkBuilder {
+        self.vcs_ignore = !yes;
+        self
+    }
+
+    /// When `yes` is `true`, `.ignore` files are not loaded.
+    pub fn no_ignore(mut self, yes: bool) -> WalkBuilder {
+        self.ignore_file = !yes;
+        self
+    }
+
+    /// Registers allow/deny globs that are consulted last and win over every
+    /// `.gitignore`/`.ignore` loaded from disk. Lines use gitignore syntax: a
+    /// plain glob denies (ignores) the path it matches, a `!`-prefixed glob
+    /// allows (re-includes) it.
+    pub fn overrides(mut self, lines: &[&str]) -> WalkBuilder {
+        self.overrides = lines.iter().map(|line| (*line).to_string()).collect();
+        self
+    }
+
+    /// Loads the applicable `.gitignore`/`.ignore` files and returns the
+    /// ready-to-use walk. Only ancestors of `self.root` are loaded up front;
+    /// `Walk` discovers each subdirectory's own ignore files itself as it
+    /// descends into it, so loading them here too would read and compile
+    /// every nested file twice.
+    pub fn build(&self) -> io::Result<Walk> {
+        let mut ignore =
+            Gitignore::from_ancestors_only(&self.root, self.vcs_ignore, self.ignore_file)?;
+
+        if !self.overrides.is_empty() {
+            let refs: Vec<&str> = self.overrides.iter().map(String::as_str).collect();
+            ignore.set_overrides(&refs);
+        }
+
+        Ok(Walk {
+            root: self.root.clone(),
+            frames: Vec::new(),
+            ignore,
+            started: false,
+            vcs_ignore: self.vcs_ignore,
+            ignore_file: self.ignore_file,
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use gitignored::Gitignore;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn loads_gitignore_found_walking_up_to_git_root() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path();
+
+    fs::create_dir_all(root.join(".git"))?;
+    fs::write(root.join(".gitignore"), "*.log\n")?;
+    fs::write(root.join("keep.rs"), "")?;
+    fs::write(root.join("debug.log"), "")?;
+
+    let ig = Gitignore::from_path(root)?;
+
+    assert!(ig.ignores_path(root.join("debug.log")));
+    assert!(!ig.ignores_path(root.join("keep.rs")));
+
+    dir.close()
+}
+
+#[test]
+fn nested_gitignore_overrides_root_for_its_own_subtree() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path();
+
+    fs::create_dir_all(root.join(".git"))?;
+    fs::create_dir_all(root.join("sub"))?;
+    fs::write(root.join(".gitignore"), "*.log\n")?;
+    fs::write(root.join("sub/.gitignore"), "private.txt\n!debug.log\n")?;
+    fs::write(root.join("root.log"), "")?;
+    fs::write(root.join("sub/private.txt"), "")?;
+    fs::write(root.join("sub/debug.log"), "")?;
+
+    let ig = Gitignore::from_path(root)?;
+
+    assert!(ig.ignores_path(root.join("root.log")));
+    assert!(ig.ignores_path(root.join("sub/private.txt")));
+    assert!(!ig.ignores_path(root.join("sub/debug.log")));
+
+    dir.close()
+}
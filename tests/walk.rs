@@ -1,5 +1,5 @@
 use fs::File;
-use gitignored::Gitignore;
+use gitignored::{Gitignore, Walk, WalkBuilder};
 use std::env;
 use std::fs;
 use std::io::Write as _;
@@ -71,3 +71,88 @@ fn it_includes_unignored_paths() -> std::io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn walk_prunes_ignored_directories() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path();
+
+    fs::create_dir_all(root.join("src"))?;
+    fs::create_dir_all(root.join("target/debug"))?;
+
+    fs::write(root.join(".gitignore"), "target/\n")?;
+    let _b = File::create(root.join("src/a.rs"))?;
+    let _c = File::create(root.join("target/debug/binary"))?;
+    let _d = File::create(root.join("Cargo.toml"))?;
+
+    let walk = Walk::new(root)?;
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for entry in walk {
+        let entry = entry?;
+        paths.push(entry.path().to_owned());
+    }
+
+    assert!(
+        !paths.iter().any(|p| p.starts_with(root.join("target"))),
+        "an ignored directory's contents must never be yielded: {:?}",
+        paths
+    );
+    assert!(paths.contains(&root.join("src/a.rs")));
+    assert!(paths.contains(&root.join("Cargo.toml")));
+
+    drop(_b);
+    drop(_c);
+    drop(_d);
+
+    dir.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn walk_builder_toggles_sources_independently() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let root = dir.path();
+
+    fs::write(root.join(".gitignore"), "*.log\n")?;
+    fs::write(root.join(".ignore"), "*.tmp\n")?;
+    let _a = File::create(root.join("keep.rs"))?;
+    let _b = File::create(root.join("debug.log"))?;
+    let _c = File::create(root.join("scratch.tmp"))?;
+
+    let paths_of = |walk: Walk| -> Vec<PathBuf> {
+        walk.filter_map(|entry| entry.ok())
+            .map(|entry| entry.path().to_owned())
+            .collect()
+    };
+
+    let both = paths_of(WalkBuilder::new(root).build()?);
+    assert!(!both.contains(&root.join("debug.log")));
+    assert!(!both.contains(&root.join("scratch.tmp")));
+    assert!(both.contains(&root.join("keep.rs")));
+
+    let no_vcs_ignore = paths_of(WalkBuilder::new(root).no_vcs_ignore(true).build()?);
+    assert!(no_vcs_ignore.contains(&root.join("debug.log")));
+    assert!(!no_vcs_ignore.contains(&root.join("scratch.tmp")));
+
+    let no_ignore = paths_of(WalkBuilder::new(root).no_ignore(true).build()?);
+    assert!(!no_ignore.contains(&root.join("debug.log")));
+    assert!(no_ignore.contains(&root.join("scratch.tmp")));
+
+    let overridden = paths_of(
+        WalkBuilder::new(root)
+            .overrides(&["!debug.log"])
+            .build()?,
+    );
+    assert!(overridden.contains(&root.join("debug.log")));
+    assert!(!overridden.contains(&root.join("scratch.tmp")));
+
+    drop(_a);
+    drop(_b);
+    drop(_c);
+
+    dir.close()?;
+
+    Ok(())
+}